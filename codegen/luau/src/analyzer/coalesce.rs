@@ -0,0 +1,360 @@
+use wasm_ast::{
+	node::{Block, Br, Expression, FuncData, LabelType, Statement, Temporary, Terminator},
+	visit::{Driver, Visitor},
+};
+
+#[derive(Clone, PartialEq, Eq)]
+struct TempSet {
+	data: Vec<u64>,
+}
+
+impl TempSet {
+	fn new(len: usize) -> Self {
+		Self {
+			data: vec![0; len.div_ceil(64)],
+		}
+	}
+
+	fn contains(&self, var: usize) -> bool {
+		self.data[var / 64] & (1 << (var % 64)) != 0
+	}
+
+	fn insert(&mut self, var: usize) {
+		self.data[var / 64] |= 1 << (var % 64);
+	}
+
+	fn remove(&mut self, var: usize) {
+		self.data[var / 64] &= !(1 << (var % 64));
+	}
+
+	fn extend(&mut self, other: &Self) {
+		for (a, b) in self.data.iter_mut().zip(&other.data) {
+			*a |= b;
+		}
+	}
+
+	fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+		self.data.iter().enumerate().flat_map(|(i, &word)| {
+			let mut word = word;
+
+			std::iter::from_fn(move || {
+				if word == 0 {
+					return None;
+				}
+
+				let bit = usize::try_from(word.trailing_zeros()).unwrap();
+
+				word &= word - 1;
+
+				Some(i * 64 + bit)
+			})
+		})
+	}
+}
+
+struct Uses<'a> {
+	list: &'a mut Vec<usize>,
+}
+
+impl Visitor for Uses<'_> {
+	fn visit_get_temporary(&mut self, temporary: Temporary) {
+		self.list.push(temporary.var());
+	}
+}
+
+// A single step of the function, reading its `uses` before writing
+// all of its `defs` at once and moving on to any of `succ`.
+#[derive(Default)]
+struct Node {
+	uses: Vec<usize>,
+	defs: Vec<usize>,
+	succ: Vec<usize>,
+}
+
+impl Node {
+	fn add_uses(&mut self, data: &Expression) {
+		data.accept(&mut Uses {
+			list: &mut self.uses,
+		});
+	}
+}
+
+// The structured code is flattened into a graph of nodes so liveness
+// can be solved with one worklist, however deeply the loops are nested.
+struct Visit {
+	node_list: Vec<Node>,
+	label_list: Vec<usize>,
+}
+
+impl Visit {
+	fn push_node(&mut self, node: Node) -> usize {
+		self.node_list.push(node);
+		self.node_list.len() - 1
+	}
+
+	fn visit_br(&mut self, br: Br) -> usize {
+		let target = self.label_list[self.label_list.len() - 1 - br.target()];
+		let align = br.align();
+		let mut node = Node {
+			succ: vec![target],
+			..Node::default()
+		};
+
+		if !align.is_aligned() {
+			node.uses
+				.extend(align.old_range().iter().map(Temporary::var));
+			node.defs
+				.extend(align.new_range().iter().map(Temporary::var));
+		}
+
+		self.push_node(node)
+	}
+
+	fn visit_terminator(&mut self, term: &Terminator) -> usize {
+		match term {
+			Terminator::Unreachable => self.push_node(Node::default()),
+			Terminator::Br(br) => self.visit_br(*br),
+			Terminator::BrTable(table) => {
+				let mut node = Node::default();
+
+				node.succ.push(self.visit_br(table.default()));
+
+				for &br in table.data() {
+					node.succ.push(self.visit_br(br));
+				}
+
+				node.add_uses(table.condition());
+
+				self.push_node(node)
+			}
+		}
+	}
+
+	fn visit_block_body(&mut self, block: &Block, next: usize) -> usize {
+		let mut entry = block
+			.last()
+			.map_or(next, |term| self.visit_terminator(term));
+
+		for stat in block.code().iter().rev() {
+			entry = self.visit_statement(stat, entry);
+		}
+
+		entry
+	}
+
+	// Branches to a loop go back to its start, which is only known once
+	// the body is built, so they target an empty node leading into it.
+	fn visit_block(&mut self, block: &Block, next: usize) -> usize {
+		if block.label_type() == Some(LabelType::Backward) {
+			let head = self.push_node(Node::default());
+
+			self.label_list.push(head);
+
+			let entry = self.visit_block_body(block, next);
+
+			self.label_list.pop();
+			self.node_list[head].succ.push(entry);
+
+			head
+		} else {
+			self.label_list.push(next);
+
+			let entry = self.visit_block_body(block, next);
+
+			self.label_list.pop();
+
+			entry
+		}
+	}
+
+	fn visit_statement(&mut self, stat: &Statement, next: usize) -> usize {
+		let mut node = Node {
+			succ: vec![next],
+			..Node::default()
+		};
+
+		match stat {
+			Statement::Block(block) => return self.visit_block(block, next),
+			Statement::BrIf(br_if) => {
+				node.succ.push(self.visit_br(br_if.target()));
+				node.add_uses(br_if.condition());
+			}
+			Statement::If(data) => {
+				let on_true = self.visit_block(data.on_true(), next);
+				let on_false = data
+					.on_false()
+					.map_or(next, |block| self.visit_block(block, next));
+
+				node.succ = vec![on_true, on_false];
+				node.add_uses(data.condition());
+			}
+			Statement::Call(call) => {
+				node.defs
+					.extend(call.result_list().iter().map(Temporary::var));
+				call.param_list().iter().for_each(|v| node.add_uses(v));
+			}
+			Statement::CallIndirect(call) => {
+				node.defs
+					.extend(call.result_list().iter().map(Temporary::var));
+				node.add_uses(call.index());
+				call.param_list().iter().for_each(|v| node.add_uses(v));
+			}
+			Statement::SetTemporary(set) => {
+				node.defs.push(set.var().var());
+				node.add_uses(set.value());
+			}
+			Statement::SetLocal(set) => node.add_uses(set.value()),
+			Statement::SetGlobal(set) => node.add_uses(set.value()),
+			Statement::StoreAt(store) => {
+				node.add_uses(store.pointer());
+				node.add_uses(store.value());
+			}
+			Statement::MemoryGrow(grow) => {
+				node.defs.push(grow.result().var());
+				node.add_uses(grow.size());
+			}
+			Statement::MemoryCopy(copy) => {
+				node.add_uses(copy.destination().pointer());
+				node.add_uses(copy.source().pointer());
+				node.add_uses(copy.size());
+			}
+			Statement::MemoryFill(fill) => {
+				node.add_uses(fill.destination().pointer());
+				node.add_uses(fill.size());
+				node.add_uses(fill.value());
+			}
+		}
+
+		self.push_node(node)
+	}
+
+	fn get_live_out(&self, live_list: &[TempSet], index: usize, len: usize) -> TempSet {
+		let mut live = TempSet::new(len);
+
+		for &succ in &self.node_list[index].succ {
+			live.extend(&live_list[succ]);
+		}
+
+		live
+	}
+
+	// Nodes are created from the end of the function backwards, so going
+	// through them in order settles most of them on the first visit.
+	fn get_live_list(&self, len: usize) -> Vec<TempSet> {
+		let mut pred_list = vec![Vec::new(); self.node_list.len()];
+		let mut live_list = vec![TempSet::new(len); self.node_list.len()];
+
+		for (i, node) in self.node_list.iter().enumerate() {
+			for &succ in &node.succ {
+				pred_list[succ].push(i);
+			}
+		}
+
+		let mut pending = vec![true; self.node_list.len()];
+		let mut work_list: Vec<_> = (0..self.node_list.len()).rev().collect();
+
+		while let Some(index) = work_list.pop() {
+			let node = &self.node_list[index];
+			let mut live = self.get_live_out(&live_list, index, len);
+
+			pending[index] = false;
+
+			node.defs.iter().for_each(|&v| live.remove(v));
+			node.uses.iter().for_each(|&v| live.insert(v));
+
+			if live == live_list[index] {
+				continue;
+			}
+
+			live_list[index] = live;
+
+			for &pred in &pred_list[index] {
+				if !pending[pred] {
+					pending[pred] = true;
+					work_list.push(pred);
+				}
+			}
+		}
+
+		live_list
+	}
+
+	// All writes of one node happen at once, so they interfere with
+	// each other as well as with whatever is live afterwards.
+	fn get_interference(&self, len: usize) -> (Vec<TempSet>, TempSet) {
+		let live_list = self.get_live_list(len);
+		let mut interference = vec![TempSet::new(len); len];
+		let mut referenced = TempSet::new(len);
+
+		for (i, node) in self.node_list.iter().enumerate() {
+			node.uses.iter().for_each(|&v| referenced.insert(v));
+
+			if node.defs.is_empty() {
+				continue;
+			}
+
+			let live = self.get_live_out(&live_list, i, len);
+
+			for (j, &var) in node.defs.iter().enumerate() {
+				let rest = node.defs[j + 1..].iter().copied();
+
+				referenced.insert(var);
+
+				for other in rest.chain(live.iter()).filter(|&v| v != var) {
+					interference[var].insert(other);
+					interference[other].insert(var);
+				}
+			}
+		}
+
+		(interference, referenced)
+	}
+
+	// Registers are handed out greedily in order, taking the lowest one
+	// not already held by an interfering temporary.
+	fn into_register_map(self, len: usize) -> (Vec<usize>, usize) {
+		let (interference, referenced) = self.get_interference(len);
+		let mut map = vec![0; len];
+		let mut num_register = 0;
+
+		for var in referenced.iter() {
+			let mut taken = TempSet::new(num_register + 1);
+
+			for other in interference[var].iter().filter(|&v| v < var) {
+				taken.insert(map[other]);
+			}
+
+			let register = (0..=num_register).find(|&v| !taken.contains(v)).unwrap();
+
+			map[var] = register;
+			num_register = num_register.max(register + 1);
+		}
+
+		(map, num_register)
+	}
+}
+
+pub fn visit(ast: &FuncData) -> (Vec<usize>, usize) {
+	let mut visit = Visit {
+		node_list: Vec::new(),
+		label_list: Vec::new(),
+	};
+
+	let exit = visit.push_node(Node {
+		uses: (0..ast.num_result()).collect(),
+		..Node::default()
+	});
+
+	visit.visit_block(ast.code(), exit);
+
+	// Unreachable code may name slots past the recorded stack size, so the
+	// analysis is sized by every temporary that actually appears.
+	let len = visit
+		.node_list
+		.iter()
+		.flat_map(|v| v.uses.iter().chain(&v.defs))
+		.map(|v| v + 1)
+		.fold(ast.num_stack().max(ast.num_result()), usize::max);
+
+	visit.into_register_map(len)
+}
@@ -1,3 +1,5 @@
+use std::collections::{HashMap, HashSet};
+
 use wasm_ast::node::{BinOpType, CmpOpType, LoadType, StoreType, UnOpType};
 
 pub trait IntoName {
@@ -237,3 +239,54 @@ impl TryIntoSymbol for CmpOpType {
 		Some(result)
 	}
 }
+
+// Names from the custom name section can contain anything, so they are
+// reduced to the characters that are valid in a Lua identifier. Since every
+// identifier gets a prefix, reserved words and runtime names can't be hit.
+fn sanitize_identifier(name: &str) -> String {
+	name.chars()
+		.map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+		.collect()
+}
+
+// Names inside of a long comment must not close it early.
+#[must_use]
+pub fn sanitize_comment(name: &str) -> String {
+	name.replace("]]", "] ]")
+}
+
+// Assign a `loc_` identifier to every named local, falling back to a
+// suffixed version whenever the name collides with another local, the
+// numeric `loc_N` scheme, or the `loc_spill` table.
+pub fn into_local_name_list<'a, I>(list: I) -> HashMap<usize, String>
+where
+	I: IntoIterator<Item = (usize, &'a str)>,
+{
+	let mut list: Vec<_> = list.into_iter().collect();
+	let mut used = HashSet::from(["loc_spill".to_string()]);
+
+	list.sort_unstable_by_key(|v| v.0);
+
+	list.into_iter()
+		.map(|(index, name)| {
+			let name = sanitize_identifier(name);
+			let mut result = if name.bytes().all(|v| v.is_ascii_digit()) {
+				format!("loc_{name}_{index}")
+			} else {
+				format!("loc_{name}")
+			};
+
+			if used.contains(&result) {
+				result = format!("loc_{name}_{index}");
+			}
+
+			while used.contains(&result) {
+				result.push('_');
+			}
+
+			used.insert(result.clone());
+
+			(index, result)
+		})
+		.collect()
+}
@@ -1,3 +1,4 @@
 pub mod br_target;
+pub mod coalesce;
 pub mod into_string;
 pub mod localize;
@@ -39,7 +39,7 @@ impl Driver for Select {
 
 impl Driver for Temporary {
 	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
-		let var = self.var();
+		let var = mng.get_register(self.var());
 
 		if let Some(var) = var.checked_sub(mng.num_temp()) {
 			write!(w, "reg_spill[{}]", var + 1)
@@ -55,6 +55,8 @@ impl Driver for Local {
 
 		if let Some(var) = var.checked_sub(mng.num_local()) {
 			write!(w, "loc_spill[{}]", var + 1)
+		} else if let Some(name) = mng.local_name(var) {
+			write!(w, "{name}")
 		} else {
 			write!(w, "loc_{var}")
 		}
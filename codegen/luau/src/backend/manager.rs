@@ -3,9 +3,12 @@ use std::{
 	io::{Result, Write},
 };
 
-use wasm_ast::node::{BrTable, FuncData, LabelType};
+use wasm_ast::{
+	module::TypeInfo,
+	node::{BrTable, FuncData, LabelType},
+};
 
-use crate::analyzer::{br_target, localize};
+use crate::analyzer::{br_target, coalesce, into_string, localize};
 
 #[macro_export]
 macro_rules! indentation {
@@ -50,20 +53,28 @@ fn get_pinned_registers(
 	(params + locals, temporaries)
 }
 
-pub struct Manager {
+pub struct Manager<'a> {
+	type_info: Option<&'a TypeInfo<'a>>,
+	local_name_list: HashMap<usize, String>,
 	table_map: HashMap<usize, usize>,
+	register_map: Vec<usize>,
 	has_branch: bool,
+	num_register: usize,
 	num_local: usize,
 	num_temp: usize,
 	label_list: Vec<Option<LabelType>>,
 	indentation: usize,
 }
 
-impl Manager {
+impl<'a> Manager<'a> {
 	pub fn empty() -> Self {
 		Self {
+			type_info: None,
+			local_name_list: HashMap::new(),
 			table_map: HashMap::new(),
+			register_map: Vec::new(),
 			has_branch: false,
+			num_register: 0,
 			num_local: 0,
 			num_temp: usize::MAX,
 			label_list: Vec::new(),
@@ -71,19 +82,31 @@ impl Manager {
 		}
 	}
 
-	pub fn function(ast: &FuncData) -> Self {
+	pub fn function(ast: &FuncData, type_info: &'a TypeInfo<'a>, index: Option<usize>) -> Self {
 		let (upvalues, memories) = localize::visit(ast);
 		let (table_map, has_branch) = br_target::visit(ast);
+		let (register_map, num_register) = coalesce::visit(ast);
 		let (num_local, num_temp) = get_pinned_registers(
 			upvalues.len() + memories.len(),
 			ast.num_param(),
 			ast.local_data().len(),
-			ast.num_stack(),
+			num_register,
 		);
 
+		let local_name_list = index.map_or_else(HashMap::new, |index| {
+			let len = ast.num_param() + ast.local_data().len();
+			let iter = (0..len).filter_map(|v| Some((v, type_info.local_name(index, v)?)));
+
+			into_string::into_local_name_list(iter)
+		});
+
 		Self {
+			type_info: Some(type_info),
+			local_name_list,
 			table_map,
+			register_map,
 			has_branch,
+			num_register,
 			num_local,
 			num_temp,
 			label_list: Vec::new(),
@@ -105,6 +128,24 @@ impl Manager {
 		self.has_branch
 	}
 
+	pub fn func_name(&self, index: usize) -> Option<&'a str> {
+		self.type_info?.func_name(index)
+	}
+
+	pub fn local_name(&self, index: usize) -> Option<&str> {
+		self.local_name_list.get(&index).map(String::as_str)
+	}
+
+	// Temporaries outside the map are left as is, which keeps the
+	// empty manager usable for the constant initializers.
+	pub fn get_register(&self, var: usize) -> usize {
+		self.register_map.get(var).copied().unwrap_or(var)
+	}
+
+	pub const fn num_register(&self) -> usize {
+		self.num_register
+	}
+
 	pub const fn num_local(&self) -> usize {
 		self.num_local
 	}
@@ -10,7 +10,9 @@ use wasm_ast::node::{
 use wasmparser::ValType;
 
 use crate::{
-	analyzer::into_string::IntoName, backend::manager::write_separated, indentation, indented, line,
+	analyzer::into_string::{sanitize_comment, IntoName},
+	backend::manager::write_separated,
+	indentation, indented, line,
 };
 
 use super::{
@@ -244,7 +246,12 @@ impl Driver for Call {
 
 		write!(w, "FUNC_LIST[{}](", self.function())?;
 		self.param_list().write(mng, w)?;
-		write!(w, ")")
+		write!(w, ")")?;
+
+		mng.func_name(self.function()).map_or_else(
+			|| Ok(()),
+			|name| write!(w, " --[[ {} ]]", sanitize_comment(name)),
+		)
 	}
 }
 
@@ -370,9 +377,16 @@ impl Driver for Statement {
 	}
 }
 
-fn write_parameter_list(ast: &FuncData, w: &mut dyn Write) -> Result<()> {
+fn write_local_name(index: usize, mng: &Manager, w: &mut dyn Write) -> Result<()> {
+	match mng.local_name(index) {
+		Some(name) => write!(w, "{name}"),
+		None => write!(w, "loc_{index}"),
+	}
+}
+
+fn write_parameter_list(ast: &FuncData, mng: &Manager, w: &mut dyn Write) -> Result<()> {
 	write!(w, "function(")?;
-	write_separated(0..ast.num_param(), |i, w| write!(w, "loc_{i}"), w)?;
+	write_separated(0..ast.num_param(), |i, w| write_local_name(i, mng, w), w)?;
 	writeln!(w, ")")
 }
 
@@ -392,7 +406,9 @@ fn write_variable_list(ast: &FuncData, mng: &Manager, w: &mut dyn Write) -> Resu
 		let index = ast.num_param() + i;
 		let zero = type_to_zero(typ);
 
-		line!(mng, w, "local loc_{index} = {zero}")?;
+		indented!(mng, w, "local ")?;
+		write_local_name(index, mng, w)?;
+		writeln!(w, " = {zero}")?;
 	}
 
 	if locals.len() != 0 {
@@ -407,7 +423,7 @@ fn write_variable_list(ast: &FuncData, mng: &Manager, w: &mut dyn Write) -> Resu
 		writeln!(w, "}}")?;
 	}
 
-	let mut temporaries = 0..ast.num_stack();
+	let mut temporaries = 0..mng.num_register();
 
 	for i in temporaries.by_ref().take(mng.num_temp()) {
 		line!(mng, w, "local reg_{i}")?;
@@ -426,7 +442,7 @@ impl Driver for FuncData {
 	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
 		mng.indent();
 
-		write_parameter_list(self, w)?;
+		write_parameter_list(self, mng, w)?;
 		write_variable_list(self, mng, w)?;
 
 		if mng.has_branch() {
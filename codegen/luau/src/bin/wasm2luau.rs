@@ -1,19 +1,63 @@
 use std::io::{ErrorKind, Result, Write};
 
-use wasm_ast::module::Module;
+use wasm_ast::module::{Module, TypeInfo};
 
-fn load_arg_source() -> Result<Vec<u8>> {
+#[derive(Default)]
+struct Options {
+	typed: bool,
+	export_runtime: bool,
+	output: Option<String>,
+	input: Option<String>,
+}
+
+fn print_usage(path: &str) {
+	eprintln!("usage: {path} [--typed] [--export-runtime] [-o <output>] <file>\n");
+	eprintln!("  --typed           translate through `from_module_typed`");
+	eprintln!(
+		"  --export-runtime  return `{{ rt = <runtime>, load = <loader> }}` instead of the loader"
+	);
+	eprintln!("  -o <output>       write to a file once translation succeeds\n");
+}
+
+// Anything that is not a known flag is taken as the input, and any
+// arguments after it are ignored as they always have been.
+fn load_arg_options() -> Result<Options> {
 	let mut arguments = std::env::args();
 	let path = arguments.next().unwrap_or_else(|| "wasm2luau".to_string());
+	let mut options = Options::default();
+
+	while let Some(argument) = arguments.next() {
+		match argument.as_str() {
+			"--typed" => options.typed = true,
+			"--export-runtime" => options.export_runtime = true,
+			"-o" | "--output" => {
+				if options.output.is_some() {
+					eprintln!("`{argument}` can only be given once");
+					print_usage(&path);
+
+					return Err(ErrorKind::InvalidInput.into());
+				}
+
+				options.output = arguments.next();
 
-	arguments.next().map_or_else(
-		|| {
-			eprintln!("usage: {path} <file>\n");
+				if options.output.is_none() {
+					print_usage(&path);
 
-			Err(ErrorKind::NotFound.into())
-		},
-		std::fs::read,
-	)
+					return Err(ErrorKind::InvalidInput.into());
+				}
+			}
+			_ if options.input.is_none() => options.input = Some(argument),
+			_ => {}
+		}
+	}
+
+	if options.input.is_none() {
+		print_usage(&path);
+
+		return Err(ErrorKind::NotFound.into());
+	}
+
+	Ok(options)
 }
 
 fn do_runtime(lock: &mut dyn Write) -> Result<()> {
@@ -23,12 +67,58 @@ fn do_runtime(lock: &mut dyn Write) -> Result<()> {
 	writeln!(lock, "{runtime}")
 }
 
-fn main() -> Result<()> {
-	let data = load_arg_source()?;
-	let wasm = Module::try_from_data(&data).unwrap();
+fn do_translate(options: &Options, wasm: &Module, lock: &mut dyn Write) -> Result<()> {
+	if options.typed {
+		let type_info = TypeInfo::from_module(wasm);
+
+		codegen_luau::from_module_typed(wasm, &type_info, lock)
+	} else {
+		codegen_luau::from_module_untyped(wasm, lock)
+	}
+}
 
-	let lock = &mut std::io::stdout().lock();
+// The module is wrapped so the chunk can hand back the runtime's `rt`
+// table next to the loader, without needing an instance first.
+fn do_export_runtime(options: &Options, wasm: &Module, lock: &mut dyn Write) -> Result<()> {
+	let runtime = codegen_luau::EXPORT_RUNTIME;
+
+	writeln!(lock, "local load_module = (function()")?;
+	do_translate(options, wasm, lock)?;
+	writeln!(lock, "end)()")?;
+
+	writeln!(lock, "return {{")?;
+	writeln!(lock, "{runtime}")?;
+	writeln!(lock, "\tload = load_module,")?;
+	writeln!(lock, "}}")
+}
+
+fn do_module(options: &Options, lock: &mut dyn Write) -> Result<()> {
+	let data = std::fs::read(options.input.as_ref().unwrap())?;
+	let wasm = Module::try_from_data(&data).unwrap();
 
 	do_runtime(lock)?;
-	codegen_luau::from_module_untyped(&wasm, lock)
+
+	if options.export_runtime {
+		do_export_runtime(options, &wasm, lock)
+	} else {
+		do_translate(options, &wasm, lock)
+	}
+}
+
+fn main() -> Result<()> {
+	let options = load_arg_options()?;
+
+	// The output is only touched after translating, so a failure never
+	// leaves an existing file truncated.
+	if let Some(path) = &options.output {
+		let mut data = Vec::new();
+
+		do_module(&options, &mut data)?;
+
+		std::fs::write(path, data)
+	} else {
+		let lock = &mut std::io::stdout().lock();
+
+		do_module(&options, lock)
+	}
 }
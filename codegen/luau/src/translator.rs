@@ -14,7 +14,7 @@ use wasmparser::{
 };
 
 use crate::{
-	analyzer::localize,
+	analyzer::{into_string::sanitize_comment, localize},
 	backend::manager::{Driver, Manager},
 };
 
@@ -256,23 +256,29 @@ fn write_localize_used(
 	Ok(mem_set)
 }
 
-fn write_func_start(wasm: &Module, index: u32, w: &mut dyn Write) -> Result<()> {
+fn write_func_start(type_info: &TypeInfo, index: usize, w: &mut dyn Write) -> Result<()> {
 	write!(w, "FUNC_LIST[{index}] = ")?;
 
-	wasm.name_section()
-		.get(&index)
-		.map_or_else(|| Ok(()), |name| write!(w, "--[[ {name} ]] "))
+	type_info.func_name(index).map_or_else(
+		|| Ok(()),
+		|name| write!(w, "--[[ {} ]] ", sanitize_comment(name)),
+	)
 }
 
-fn write_func_list(wasm: &Module, func_list: &[FuncData], w: &mut dyn Write) -> Result<()> {
+fn write_func_list(
+	wasm: &Module,
+	type_info: &TypeInfo,
+	func_list: &[FuncData],
+	w: &mut dyn Write,
+) -> Result<()> {
 	let offset = wasm.import_count(External::Func);
 
 	func_list.iter().enumerate().try_for_each(|(i, v)| {
-		let index = (offset + i).try_into().unwrap();
+		let index = offset + i;
 
-		write_func_start(wasm, index, w)?;
+		write_func_start(type_info, index, w)?;
 
-		v.write(&mut Manager::function(v), w)
+		v.write(&mut Manager::function(v, type_info, Some(index)), w)
 	})
 }
 
@@ -313,7 +319,13 @@ fn write_module_start(
 pub fn from_inst_list(code: &[Operator], type_info: &TypeInfo, w: &mut dyn Write) -> Result<()> {
 	let ast = Factory::from_type_info(type_info).create_anonymous(code);
 
-	ast.write(&mut Manager::function(&ast), w)
+	from_func_data(&ast, type_info, w)
+}
+
+/// # Errors
+/// Returns `Err` if writing to `Write` failed.
+pub fn from_func_data(ast: &FuncData, type_info: &TypeInfo, w: &mut dyn Write) -> Result<()> {
+	ast.write(&mut Manager::function(ast, type_info, None), w)
 }
 
 /// # Errors
@@ -327,7 +339,7 @@ pub fn from_module_typed(wasm: &Module, type_info: &TypeInfo, w: &mut dyn Write)
 	write_named_array("MEMORY_LIST", wasm.memory_space(), w)?;
 	write_named_array("GLOBAL_LIST", wasm.global_space(), w)?;
 
-	write_func_list(wasm, &func_list, w)?;
+	write_func_list(wasm, type_info, &func_list, w)?;
 	write_module_start(wasm, type_info, &mem_set, w)
 }
 
@@ -0,0 +1,49 @@
+use wasm_ast::{
+	factory::Factory,
+	module::{Module, TypeInfo},
+};
+
+mod common;
+
+#[test]
+fn typed_snippet_translates() {
+	let bytes = common::load_wat(
+		"(module (func (param i32 i32) (result i32 i32) (local i64 f32)
+			local.get 1 local.get 0
+		))",
+	);
+
+	let wasm = Module::try_from_data(&bytes).unwrap();
+	let type_info = TypeInfo::from_module(&wasm);
+	let body = &wasm.code_section()[0];
+
+	let code: Vec<_> = body
+		.get_operators_reader()
+		.unwrap()
+		.into_iter()
+		.map(Result::unwrap)
+		.collect();
+
+	let local_data = body
+		.get_locals_reader()
+		.unwrap()
+		.into_iter()
+		.flat_map(|v| {
+			let (count, typ) = v.unwrap();
+
+			std::iter::repeat_n(typ, count.try_into().unwrap())
+		})
+		.collect();
+
+	let func = Factory::from_type_info(&type_info).create_anonymous_typed(&code, local_data, 2, 2);
+	let mut data = Vec::new();
+
+	codegen_luau::from_func_data(&func, &type_info, &mut data).unwrap();
+
+	let data = String::from_utf8(data).unwrap();
+
+	assert!(data.starts_with("function(loc_0, loc_1)"));
+	assert!(data.contains("local loc_2 = rt_i64_ZERO"));
+	assert!(data.contains("local loc_3 = 0.0"));
+	assert!(data.contains("return reg_0, reg_1"));
+}
@@ -0,0 +1,86 @@
+mod common;
+
+const HEADER: &str = "(global $g (mut i32) (i32.const 0)) (func $get (result i32) i32.const 1)";
+
+#[test]
+fn disjoint_values_share_register() {
+	let body = (0..250).fold(String::new(), |body, v| {
+		let list = "i32.const 1 ".repeat(v);
+		let adds = "i32.add ".repeat(v + 1);
+
+		format!("{body}{list} global.get $g call $get {adds} global.set $g ")
+	});
+
+	let data = common::translate(&format!("(module {HEADER} (func {body}))"));
+
+	assert!(data.contains("local reg_1"));
+	assert!(!data.contains("local reg_2"));
+	assert!(!data.contains("reg_spill"));
+}
+
+#[test]
+fn value_held_across_call_is_kept() {
+	let data = common::translate(&format!(
+		"(module {HEADER} (func (result i32) global.get $g call $get i32.add))"
+	));
+
+	assert!(data.contains("reg_1 = FUNC_LIST[0]()"));
+	assert!(data.contains("rt_add_i32(reg_0, reg_1)"));
+}
+
+#[test]
+fn loop_value_is_kept_across_br_if() {
+	let data = common::translate(&format!(
+		"(module {HEADER}
+			(func (result i32)
+				i32.const 0
+				(loop (param i32)
+					global.get $g i32.add global.set $g
+					global.get $g
+					call $get
+					br_if 0
+					drop)
+				i32.const 5)
+		)"
+	));
+
+	assert!(data.contains("reg_0 = GLOBAL_LIST[0].value"));
+	assert!(data.contains("reg_1 = FUNC_LIST[0]()"));
+	assert!(data.contains("if reg_1 ~= 0 then"));
+}
+
+#[test]
+fn loop_value_is_kept_across_br_table() {
+	let data = common::translate(&format!(
+		"(module {HEADER}
+			(func (result i32)
+				(block (result i32)
+					i32.const 0
+					(loop (param i32)
+						global.get $g i32.add global.set $g
+						global.get $g
+						call $get
+						br_table 0 1))
+				drop
+				i32.const 5)
+		)"
+	));
+
+	assert!(data.contains("reg_0 = GLOBAL_LIST[0].value"));
+	assert!(data.contains("reg_1 = FUNC_LIST[0]()"));
+	assert!(data.contains("br_map[1][reg_1]"));
+}
+
+#[test]
+fn deeply_nested_loops_share_registers() {
+	let body = (0..200).fold(String::new(), |body, _| {
+		format!("(loop global.get $g call $get i32.add global.set $g {body} global.get $g br_if 0)")
+	});
+
+	let data = common::translate(&format!("(module {HEADER} (func {body}))"));
+
+	assert_eq!(data.matches("continue").count(), 200);
+	assert!(data.contains("local reg_1"));
+	assert!(!data.contains("local reg_2"));
+	assert!(!data.contains("reg_spill"));
+}
@@ -0,0 +1,26 @@
+#![allow(dead_code)]
+
+use wasm_ast::module::Module;
+use wast::{
+	parser::{self, ParseBuffer},
+	Wat,
+};
+
+pub fn load_wat(text: &str) -> Vec<u8> {
+	let buffer = ParseBuffer::new(text).unwrap();
+
+	match parser::parse::<Wat>(&buffer).unwrap() {
+		Wat::Module(mut ast) => ast.encode().unwrap(),
+		Wat::Component(_) => unimplemented!(),
+	}
+}
+
+pub fn translate(text: &str) -> String {
+	let bytes = load_wat(text);
+	let wasm = Module::try_from_data(&bytes).unwrap();
+	let mut data = Vec::new();
+
+	codegen_luau::from_module_untyped(&wasm, &mut data).unwrap();
+
+	String::from_utf8(data).unwrap()
+}
@@ -0,0 +1,210 @@
+use wasm_ast::{
+	factory::Factory,
+	fold::fold_constants,
+	module::{Module, TypeInfo},
+	node::{Expression, FuncData, Statement, Value},
+};
+
+mod common;
+
+fn load_func_data(text: &str) -> FuncData {
+	let bytes = common::load_wat(text);
+	let wasm = Module::try_from_data(&bytes).unwrap();
+	let type_info = TypeInfo::from_module(&wasm);
+	let mut func = Factory::from_type_info(&type_info)
+		.create_indexed(0, &wasm.code_section()[0])
+		.unwrap();
+
+	fold_constants(&mut func);
+	func
+}
+
+fn get_result(func: &FuncData) -> &Expression {
+	match func.code().code().last() {
+		Some(Statement::SetTemporary(stat)) => stat.value(),
+		_ => panic!("Missing result"),
+	}
+}
+
+#[test]
+fn i32_overflow_wraps() {
+	let func =
+		load_func_data("(module (func (result i32) i32.const 0x7FFFFFFF i32.const 1 i32.add))");
+
+	assert!(matches!(
+		get_result(&func),
+		Expression::Value(Value::I32(i32::MIN))
+	));
+}
+
+#[test]
+fn i32_multiply_wraps() {
+	let func =
+		load_func_data("(module (func (result i32) i32.const 0x10000 i32.const 0x10001 i32.mul))");
+
+	assert!(matches!(
+		get_result(&func),
+		Expression::Value(Value::I32(0x10000))
+	));
+}
+
+#[test]
+fn nested_operations_fold() {
+	let func = load_func_data(
+		"(module (func (result i32) i32.const 2 i32.const 3 i32.add i32.const 4 i32.lt_s))",
+	);
+
+	assert!(matches!(
+		get_result(&func),
+		Expression::Value(Value::I32(0))
+	));
+}
+
+#[test]
+fn division_by_zero_is_kept() {
+	let func = load_func_data("(module (func (result i32) i32.const 1 i32.const 0 i32.div_s))");
+
+	assert!(matches!(get_result(&func), Expression::BinOp(_)));
+}
+
+#[test]
+fn signed_division_overflow_is_kept() {
+	let func =
+		load_func_data("(module (func (result i32) i32.const 0x80000000 i32.const -1 i32.div_s))");
+
+	assert!(matches!(get_result(&func), Expression::BinOp(_)));
+}
+
+#[test]
+fn nan_is_kept() {
+	let func =
+		load_func_data("(module (func (result f32) f32.const nan:0x200000 f32.const 1 f32.add))");
+
+	assert!(matches!(get_result(&func), Expression::BinOp(_)));
+}
+
+#[test]
+fn i64_overflow_wraps() {
+	let func = load_func_data(
+		"(module (func (result i64) i64.const 0x7FFFFFFFFFFFFFFF i64.const 1 i64.add))",
+	);
+
+	assert!(matches!(
+		get_result(&func),
+		Expression::Value(Value::I64(i64::MIN))
+	));
+}
+
+#[test]
+fn i64_division_by_zero_is_kept() {
+	let func = load_func_data("(module (func (result i64) i64.const 1 i64.const 0 i64.div_u))");
+
+	assert!(matches!(get_result(&func), Expression::BinOp(_)));
+}
+
+#[test]
+fn i32_shift_count_wraps() {
+	let func = load_func_data("(module (func (result i32) i32.const 1 i32.const 33 i32.shl))");
+
+	assert!(matches!(
+		get_result(&func),
+		Expression::Value(Value::I32(2))
+	));
+}
+
+#[test]
+fn i64_shift_count_wraps() {
+	let func = load_func_data("(module (func (result i64) i64.const -8 i64.const 65 i64.shr_s))");
+
+	assert!(matches!(
+		get_result(&func),
+		Expression::Value(Value::I64(-4))
+	));
+}
+
+#[test]
+fn signed_remainder_overflow_is_zero() {
+	let func =
+		load_func_data("(module (func (result i32) i32.const 0x80000000 i32.const -1 i32.rem_s))");
+
+	assert!(matches!(
+		get_result(&func),
+		Expression::Value(Value::I32(0))
+	));
+}
+
+#[test]
+fn truncate_in_range_folds() {
+	let func = load_func_data("(module (func (result i32) f64.const -1.5 i32.trunc_f64_s))");
+
+	assert!(matches!(
+		get_result(&func),
+		Expression::Value(Value::I32(-1))
+	));
+}
+
+#[test]
+fn truncate_out_of_range_is_kept() {
+	let func = load_func_data("(module (func (result i32) f32.const 3e9 i32.trunc_f32_s))");
+
+	assert!(matches!(get_result(&func), Expression::UnOp(_)));
+}
+
+#[test]
+fn min_of_zeroes_is_negative() {
+	let func = load_func_data("(module (func (result f32) f32.const 0 f32.const -0 f32.min))");
+
+	assert!(matches!(
+		get_result(&func),
+		Expression::Value(Value::F32(v)) if *v == 0.0 && v.is_sign_negative()
+	));
+}
+
+#[test]
+fn max_of_zeroes_is_positive() {
+	let func = load_func_data("(module (func (result f64) f64.const -0 f64.const 0 f64.max))");
+
+	assert!(matches!(
+		get_result(&func),
+		Expression::Value(Value::F64(v)) if *v == 0.0 && v.is_sign_positive()
+	));
+}
+
+#[test]
+fn select_operands_fold() {
+	let func = load_func_data(
+		"(module (func (result i32)
+			i32.const 1 i32.const 2 i32.add
+			i32.const 4
+			i32.const 0 i32.eqz
+			select
+		))",
+	);
+
+	let Expression::Select(select) = get_result(&func) else {
+		panic!("Missing select")
+	};
+
+	assert!(matches!(
+		select.condition(),
+		Expression::Value(Value::I32(1))
+	));
+	assert!(matches!(select.on_true(), Expression::Value(Value::I32(3))));
+	assert!(matches!(
+		select.on_false(),
+		Expression::Value(Value::I32(4))
+	));
+}
+
+#[test]
+fn load_pointer_folds() {
+	let func = load_func_data(
+		"(module (memory 1) (func (result i32) i32.const 4 i32.const 8 i32.add i32.load))",
+	);
+
+	let Expression::LoadAt(load) = get_result(&func) else {
+		panic!("Missing load")
+	};
+
+	assert!(matches!(load.pointer(), Expression::Value(Value::I32(12))));
+}
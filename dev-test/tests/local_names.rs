@@ -0,0 +1,70 @@
+mod common;
+
+#[test]
+fn named_locals_are_used() {
+	let data = common::translate(
+		"(module
+			(func (param $lhs i32) (param $rhs i32) (local $sum i32) (local i32)
+				local.get $lhs local.get $rhs i32.add local.set $sum)
+		)",
+	);
+
+	assert!(data.contains("function(loc_lhs, loc_rhs)"));
+	assert!(data.contains("local loc_sum = 0"));
+	assert!(data.contains("local loc_3 = 0"));
+	assert!(data.contains("loc_sum = rt_add_i32(loc_lhs, loc_rhs)"));
+}
+
+#[test]
+fn unnamed_locals_keep_numbers() {
+	let data = common::translate("(module (func (param i32) (local i32) local.get 0 local.set 1))");
+
+	assert!(data.contains("function(loc_0)"));
+	assert!(data.contains("local loc_1 = 0"));
+	assert!(data.contains("loc_1 = loc_0"));
+	assert!(!data.contains("--[["));
+}
+
+#[test]
+fn numeric_name_is_suffixed() {
+	let data = common::translate("(module (func (param $3 i32) (param i32)))");
+
+	assert!(data.contains("function(loc_3_0, loc_1)"));
+}
+
+#[test]
+fn spill_name_is_suffixed() {
+	let data = common::translate("(module (func (param $spill i32)))");
+
+	assert!(data.contains("function(loc_spill_0)"));
+}
+
+#[test]
+fn duplicate_name_is_suffixed() {
+	let data =
+		common::translate(r#"(module (func (param (@name "dup") i32) (param (@name "dup") i32)))"#);
+
+	assert!(data.contains("function(loc_dup, loc_dup_1)"));
+}
+
+#[test]
+fn invalid_characters_are_replaced() {
+	let data = common::translate(
+		r#"(module (func (param (@name "") i32) (param (@name "héllo world") i32)))"#,
+	);
+
+	assert!(data.contains("function(loc__0, loc_h_llo_world)"));
+}
+
+#[test]
+fn call_is_commented_with_callee() {
+	let data = common::translate(
+		r#"(module
+			(func $get (@name "a]]b") (result i32) i32.const 1)
+			(func (result i32) call $get)
+		)"#,
+	);
+
+	assert!(data.contains("FUNC_LIST[0] = --[[ a] ]b ]] function()"));
+	assert!(data.contains("= FUNC_LIST[0]() --[[ a] ]b ]]"));
+}
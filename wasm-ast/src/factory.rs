@@ -1,4 +1,4 @@
-use wasmparser::{BlockType, FunctionBody, MemArg, Operator, Result};
+use wasmparser::{BlockType, FunctionBody, MemArg, Operator, Result, ValType};
 
 use crate::{
 	module::{read_checked, read_checked_locals, TypeInfo},
@@ -230,12 +230,26 @@ impl<'a> Factory<'a> {
 
 	#[must_use]
 	pub fn create_anonymous(&mut self, list: &[Operator]) -> FuncData {
-		let data = self.build_stat_list(list, 1);
+		self.create_anonymous_typed(list, Vec::new(), 0, 1)
+	}
+
+	/// Builds a function from a standalone operator sequence ending with
+	/// `End`. The `local_data` holds the types of the locals declared
+	/// after the parameters.
+	#[must_use]
+	pub fn create_anonymous_typed(
+		&mut self,
+		list: &[Operator],
+		local_data: Vec<ValType>,
+		num_param: usize,
+		num_result: usize,
+	) -> FuncData {
+		let data = self.build_stat_list(list, num_result);
 
 		FuncData {
-			local_data: Vec::new(),
-			num_result: 1,
-			num_param: 0,
+			local_data,
+			num_result,
+			num_param,
 			num_stack: data.stack.capacity,
 			code: data.into(),
 		}
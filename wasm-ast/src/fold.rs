@@ -0,0 +1,440 @@
+use crate::node::{
+	BinOp, BinOpType, Block, CmpOp, CmpOpType, Expression, FuncData, Statement, Terminator, UnOp,
+	UnOpType, Value,
+};
+
+// Floats are only folded when no NaN goes in or comes out, since the bit
+// pattern of a NaN is not preserved by the host arithmetic or the backends.
+trait NotNan: Sized {
+	fn not_nan(self) -> Option<Self>;
+}
+
+impl NotNan for f32 {
+	fn not_nan(self) -> Option<Self> {
+		(!self.is_nan()).then_some(self)
+	}
+}
+
+impl NotNan for f64 {
+	fn not_nan(self) -> Option<Self> {
+		(!self.is_nan()).then_some(self)
+	}
+}
+
+macro_rules! impl_min_max {
+	($min:tt, $max:tt, $numeric:ty) => {
+		// Equal operands are either identical or zeroes of differing sign,
+		// where WebAssembly orders `-0.0` below `0.0`.
+		#[allow(clippy::float_cmp)]
+		fn $min(lhs: $numeric, rhs: $numeric) -> $numeric {
+			if lhs < rhs || (lhs == rhs && lhs.is_sign_negative()) {
+				lhs
+			} else {
+				rhs
+			}
+		}
+
+		#[allow(clippy::float_cmp)]
+		fn $max(lhs: $numeric, rhs: $numeric) -> $numeric {
+			if lhs > rhs || (lhs == rhs && lhs.is_sign_positive()) {
+				lhs
+			} else {
+				rhs
+			}
+		}
+	};
+}
+
+impl_min_max!(min_f32, max_f32, f32);
+impl_min_max!(min_f64, max_f64, f64);
+
+// Truncation traps when the value does not fit, so the bounds are checked
+// exclusively in `f64` where all of them are exact.
+fn truncate_in(value: f64, min: f64, max: f64) -> Option<f64> {
+	let value = value.trunc();
+
+	(value > min && value < max).then_some(value)
+}
+
+#[allow(
+	clippy::cast_possible_truncation,
+	clippy::cast_possible_wrap,
+	clippy::cast_sign_loss
+)]
+fn fold_truncate(op_type: UnOpType, value: f64) -> Option<Value> {
+	let result = match op_type {
+		UnOpType::Truncate_I32_F32 | UnOpType::Truncate_I32_F64 => {
+			Value::I32(truncate_in(value, -2_147_483_649.0, 2_147_483_648.0)? as i32)
+		}
+		UnOpType::Truncate_U32_F32 | UnOpType::Truncate_U32_F64 => {
+			Value::I32(truncate_in(value, -1.0, 4_294_967_296.0)? as u32 as i32)
+		}
+		UnOpType::Truncate_I64_F32 | UnOpType::Truncate_I64_F64 => Value::I64(truncate_in(
+			value,
+			-9_223_372_036_854_777_856.0,
+			9_223_372_036_854_775_808.0,
+		)? as i64),
+		UnOpType::Truncate_U64_F32 | UnOpType::Truncate_U64_F64 => {
+			Value::I64(truncate_in(value, -1.0, 18_446_744_073_709_551_616.0)? as u64 as i64)
+		}
+		_ => return None,
+	};
+
+	Some(result)
+}
+
+// Rust's float to integer casts saturate and map NaN to zero,
+// which is exactly the behavior of the saturating truncations.
+#[allow(
+	clippy::cast_possible_truncation,
+	clippy::cast_possible_wrap,
+	clippy::cast_sign_loss
+)]
+const fn fold_saturate(op_type: UnOpType, value: f64) -> Option<Value> {
+	let result = match op_type {
+		UnOpType::Saturate_I32_F32 | UnOpType::Saturate_I32_F64 => Value::I32(value as i32),
+		UnOpType::Saturate_U32_F32 | UnOpType::Saturate_U32_F64 => Value::I32(value as u32 as i32),
+		UnOpType::Saturate_I64_F32 | UnOpType::Saturate_I64_F64 => Value::I64(value as i64),
+		UnOpType::Saturate_U64_F32 | UnOpType::Saturate_U64_F64 => Value::I64(value as u64 as i64),
+		_ => return None,
+	};
+
+	Some(result)
+}
+
+#[allow(
+	clippy::cast_possible_truncation,
+	clippy::cast_possible_wrap,
+	clippy::cast_precision_loss,
+	clippy::cast_sign_loss,
+	clippy::too_many_lines
+)]
+fn fold_un_op(op_type: UnOpType, rhs: Value) -> Option<Value> {
+	let result = match (op_type, rhs) {
+		(UnOpType::Clz_I32, Value::I32(v)) => Value::I32(v.leading_zeros() as i32),
+		(UnOpType::Ctz_I32, Value::I32(v)) => Value::I32(v.trailing_zeros() as i32),
+		(UnOpType::Popcnt_I32, Value::I32(v)) => Value::I32(v.count_ones() as i32),
+		(UnOpType::Clz_I64, Value::I64(v)) => Value::I64(v.leading_zeros().into()),
+		(UnOpType::Ctz_I64, Value::I64(v)) => Value::I64(v.trailing_zeros().into()),
+		(UnOpType::Popcnt_I64, Value::I64(v)) => Value::I64(v.count_ones().into()),
+		(UnOpType::Abs_F32, Value::F32(v)) => Value::F32(v.not_nan()?.abs()),
+		(UnOpType::Neg_F32, Value::F32(v)) => Value::F32(-v.not_nan()?),
+		(UnOpType::Ceil_F32, Value::F32(v)) => Value::F32(v.not_nan()?.ceil()),
+		(UnOpType::Floor_F32, Value::F32(v)) => Value::F32(v.not_nan()?.floor()),
+		(UnOpType::Truncate_F32, Value::F32(v)) => Value::F32(v.not_nan()?.trunc()),
+		(UnOpType::Nearest_F32, Value::F32(v)) => Value::F32(v.not_nan()?.round_ties_even()),
+		(UnOpType::Sqrt_F32, Value::F32(v)) => Value::F32(v.sqrt().not_nan()?),
+		(UnOpType::Abs_F64, Value::F64(v)) => Value::F64(v.not_nan()?.abs()),
+		(UnOpType::Neg_F64, Value::F64(v)) => Value::F64(-v.not_nan()?),
+		(UnOpType::Ceil_F64, Value::F64(v)) => Value::F64(v.not_nan()?.ceil()),
+		(UnOpType::Floor_F64, Value::F64(v)) => Value::F64(v.not_nan()?.floor()),
+		(UnOpType::Truncate_F64, Value::F64(v)) => Value::F64(v.not_nan()?.trunc()),
+		(UnOpType::Nearest_F64, Value::F64(v)) => Value::F64(v.not_nan()?.round_ties_even()),
+		(UnOpType::Sqrt_F64, Value::F64(v)) => Value::F64(v.sqrt().not_nan()?),
+		(UnOpType::Wrap_I32_I64, Value::I64(v)) => Value::I32(v as i32),
+		(
+			UnOpType::Truncate_I32_F32
+			| UnOpType::Truncate_U32_F32
+			| UnOpType::Truncate_I64_F32
+			| UnOpType::Truncate_U64_F32,
+			Value::F32(v),
+		) => fold_truncate(op_type, v.not_nan()?.into())?,
+		(
+			UnOpType::Truncate_I32_F64
+			| UnOpType::Truncate_U32_F64
+			| UnOpType::Truncate_I64_F64
+			| UnOpType::Truncate_U64_F64,
+			Value::F64(v),
+		) => fold_truncate(op_type, v.not_nan()?)?,
+		(
+			UnOpType::Saturate_I32_F32
+			| UnOpType::Saturate_U32_F32
+			| UnOpType::Saturate_I64_F32
+			| UnOpType::Saturate_U64_F32,
+			Value::F32(v),
+		) => fold_saturate(op_type, v.into())?,
+		(
+			UnOpType::Saturate_I32_F64
+			| UnOpType::Saturate_U32_F64
+			| UnOpType::Saturate_I64_F64
+			| UnOpType::Saturate_U64_F64,
+			Value::F64(v),
+		) => fold_saturate(op_type, v)?,
+		(UnOpType::Extend_I32_N8, Value::I32(v)) => Value::I32((v as i8).into()),
+		(UnOpType::Extend_I32_N16, Value::I32(v)) => Value::I32((v as i16).into()),
+		(UnOpType::Extend_I64_N8, Value::I64(v)) => Value::I64((v as i8).into()),
+		(UnOpType::Extend_I64_N16, Value::I64(v)) => Value::I64((v as i16).into()),
+		(UnOpType::Extend_I64_N32, Value::I64(v)) => Value::I64((v as i32).into()),
+		(UnOpType::Extend_I64_I32, Value::I32(v)) => Value::I64(v.into()),
+		(UnOpType::Extend_I64_U32, Value::I32(v)) => Value::I64((v as u32).into()),
+		(UnOpType::Convert_F32_I32, Value::I32(v)) => Value::F32(v as f32),
+		(UnOpType::Convert_F32_U32, Value::I32(v)) => Value::F32(v as u32 as f32),
+		(UnOpType::Convert_F32_I64, Value::I64(v)) => Value::F32(v as f32),
+		(UnOpType::Convert_F32_U64, Value::I64(v)) => Value::F32(v as u64 as f32),
+		(UnOpType::Demote_F32_F64, Value::F64(v)) => Value::F32(v.not_nan()? as f32),
+		(UnOpType::Convert_F64_I32, Value::I32(v)) => Value::F64(v.into()),
+		(UnOpType::Convert_F64_U32, Value::I32(v)) => Value::F64((v as u32).into()),
+		(UnOpType::Convert_F64_I64, Value::I64(v)) => Value::F64(v as f64),
+		(UnOpType::Convert_F64_U64, Value::I64(v)) => Value::F64(v as u64 as f64),
+		(UnOpType::Promote_F64_F32, Value::F32(v)) => Value::F64(v.not_nan()?.into()),
+		(UnOpType::Reinterpret_I32_F32, Value::F32(v)) => Value::I32(v.not_nan()?.to_bits() as i32),
+		(UnOpType::Reinterpret_I64_F64, Value::F64(v)) => Value::I64(v.not_nan()?.to_bits() as i64),
+		(UnOpType::Reinterpret_F32_I32, Value::I32(v)) => {
+			Value::F32(f32::from_bits(v as u32).not_nan()?)
+		}
+		(UnOpType::Reinterpret_F64_I64, Value::I64(v)) => {
+			Value::F64(f64::from_bits(v as u64).not_nan()?)
+		}
+		_ => return None,
+	};
+
+	Some(result)
+}
+
+// Division traps on a zero divisor and on signed overflow, so neither is folded.
+// Every other integer operation wraps, with shift amounts taken modulo the width.
+#[allow(clippy::cast_possible_wrap, clippy::cast_sign_loss)]
+fn fold_bin_op_i32(op_type: BinOpType, lhs: i32, rhs: i32) -> Option<i32> {
+	let shift = (rhs as u32) & 31;
+	let result = match op_type {
+		BinOpType::Add_I32 => lhs.wrapping_add(rhs),
+		BinOpType::Sub_I32 => lhs.wrapping_sub(rhs),
+		BinOpType::Mul_I32 => lhs.wrapping_mul(rhs),
+		BinOpType::DivS_I32 => lhs.checked_div(rhs)?,
+		BinOpType::DivU_I32 => (lhs as u32).checked_div(rhs as u32)? as i32,
+		BinOpType::RemS_I32 if rhs == 0 => return None,
+		BinOpType::RemS_I32 => lhs.wrapping_rem(rhs),
+		BinOpType::RemU_I32 => (lhs as u32).checked_rem(rhs as u32)? as i32,
+		BinOpType::And_I32 => lhs & rhs,
+		BinOpType::Or_I32 => lhs | rhs,
+		BinOpType::Xor_I32 => lhs ^ rhs,
+		BinOpType::Shl_I32 => lhs << shift,
+		BinOpType::ShrS_I32 => lhs >> shift,
+		BinOpType::ShrU_I32 => ((lhs as u32) >> shift) as i32,
+		BinOpType::Rotl_I32 => lhs.rotate_left(shift),
+		BinOpType::Rotr_I32 => lhs.rotate_right(shift),
+		_ => return None,
+	};
+
+	Some(result)
+}
+
+#[allow(
+	clippy::cast_possible_truncation,
+	clippy::cast_possible_wrap,
+	clippy::cast_sign_loss
+)]
+fn fold_bin_op_i64(op_type: BinOpType, lhs: i64, rhs: i64) -> Option<i64> {
+	let shift = (rhs as u32) & 63;
+	let result = match op_type {
+		BinOpType::Add_I64 => lhs.wrapping_add(rhs),
+		BinOpType::Sub_I64 => lhs.wrapping_sub(rhs),
+		BinOpType::Mul_I64 => lhs.wrapping_mul(rhs),
+		BinOpType::DivS_I64 => lhs.checked_div(rhs)?,
+		BinOpType::DivU_I64 => (lhs as u64).checked_div(rhs as u64)? as i64,
+		BinOpType::RemS_I64 if rhs == 0 => return None,
+		BinOpType::RemS_I64 => lhs.wrapping_rem(rhs),
+		BinOpType::RemU_I64 => (lhs as u64).checked_rem(rhs as u64)? as i64,
+		BinOpType::And_I64 => lhs & rhs,
+		BinOpType::Or_I64 => lhs | rhs,
+		BinOpType::Xor_I64 => lhs ^ rhs,
+		BinOpType::Shl_I64 => lhs << shift,
+		BinOpType::ShrS_I64 => lhs >> shift,
+		BinOpType::ShrU_I64 => ((lhs as u64) >> shift) as i64,
+		BinOpType::Rotl_I64 => lhs.rotate_left(shift),
+		BinOpType::Rotr_I64 => lhs.rotate_right(shift),
+		_ => return None,
+	};
+
+	Some(result)
+}
+
+fn fold_bin_op_f32(op_type: BinOpType, lhs: f32, rhs: f32) -> Option<f32> {
+	let (lhs, rhs) = (lhs.not_nan()?, rhs.not_nan()?);
+	let result = match op_type {
+		BinOpType::Add_F32 => lhs + rhs,
+		BinOpType::Sub_F32 => lhs - rhs,
+		BinOpType::Mul_F32 => lhs * rhs,
+		BinOpType::Div_F32 => lhs / rhs,
+		BinOpType::Min_F32 => min_f32(lhs, rhs),
+		BinOpType::Max_F32 => max_f32(lhs, rhs),
+		BinOpType::Copysign_F32 => lhs.copysign(rhs),
+		_ => return None,
+	};
+
+	result.not_nan()
+}
+
+fn fold_bin_op_f64(op_type: BinOpType, lhs: f64, rhs: f64) -> Option<f64> {
+	let (lhs, rhs) = (lhs.not_nan()?, rhs.not_nan()?);
+	let result = match op_type {
+		BinOpType::Add_F64 => lhs + rhs,
+		BinOpType::Sub_F64 => lhs - rhs,
+		BinOpType::Mul_F64 => lhs * rhs,
+		BinOpType::Div_F64 => lhs / rhs,
+		BinOpType::Min_F64 => min_f64(lhs, rhs),
+		BinOpType::Max_F64 => max_f64(lhs, rhs),
+		BinOpType::Copysign_F64 => lhs.copysign(rhs),
+		_ => return None,
+	};
+
+	result.not_nan()
+}
+
+fn fold_bin_op(op_type: BinOpType, lhs: Value, rhs: Value) -> Option<Value> {
+	let result = match (lhs, rhs) {
+		(Value::I32(lhs), Value::I32(rhs)) => Value::I32(fold_bin_op_i32(op_type, lhs, rhs)?),
+		(Value::I64(lhs), Value::I64(rhs)) => Value::I64(fold_bin_op_i64(op_type, lhs, rhs)?),
+		(Value::F32(lhs), Value::F32(rhs)) => Value::F32(fold_bin_op_f32(op_type, lhs, rhs)?),
+		(Value::F64(lhs), Value::F64(rhs)) => Value::F64(fold_bin_op_f64(op_type, lhs, rhs)?),
+		_ => return None,
+	};
+
+	Some(result)
+}
+
+#[allow(clippy::cast_sign_loss, clippy::float_cmp)]
+fn fold_cmp_op(op_type: CmpOpType, lhs: Value, rhs: Value) -> Option<Value> {
+	let result = match (op_type, lhs, rhs) {
+		(CmpOpType::Eq_I32, Value::I32(a), Value::I32(b)) => a == b,
+		(CmpOpType::Ne_I32, Value::I32(a), Value::I32(b)) => a != b,
+		(CmpOpType::LtS_I32, Value::I32(a), Value::I32(b)) => a < b,
+		(CmpOpType::LtU_I32, Value::I32(a), Value::I32(b)) => (a as u32) < (b as u32),
+		(CmpOpType::GtS_I32, Value::I32(a), Value::I32(b)) => a > b,
+		(CmpOpType::GtU_I32, Value::I32(a), Value::I32(b)) => (a as u32) > (b as u32),
+		(CmpOpType::LeS_I32, Value::I32(a), Value::I32(b)) => a <= b,
+		(CmpOpType::LeU_I32, Value::I32(a), Value::I32(b)) => (a as u32) <= (b as u32),
+		(CmpOpType::GeS_I32, Value::I32(a), Value::I32(b)) => a >= b,
+		(CmpOpType::GeU_I32, Value::I32(a), Value::I32(b)) => (a as u32) >= (b as u32),
+		(CmpOpType::Eq_I64, Value::I64(a), Value::I64(b)) => a == b,
+		(CmpOpType::Ne_I64, Value::I64(a), Value::I64(b)) => a != b,
+		(CmpOpType::LtS_I64, Value::I64(a), Value::I64(b)) => a < b,
+		(CmpOpType::LtU_I64, Value::I64(a), Value::I64(b)) => (a as u64) < (b as u64),
+		(CmpOpType::GtS_I64, Value::I64(a), Value::I64(b)) => a > b,
+		(CmpOpType::GtU_I64, Value::I64(a), Value::I64(b)) => (a as u64) > (b as u64),
+		(CmpOpType::LeS_I64, Value::I64(a), Value::I64(b)) => a <= b,
+		(CmpOpType::LeU_I64, Value::I64(a), Value::I64(b)) => (a as u64) <= (b as u64),
+		(CmpOpType::GeS_I64, Value::I64(a), Value::I64(b)) => a >= b,
+		(CmpOpType::GeU_I64, Value::I64(a), Value::I64(b)) => (a as u64) >= (b as u64),
+		(CmpOpType::Eq_F32, Value::F32(a), Value::F32(b)) => a == b,
+		(CmpOpType::Ne_F32, Value::F32(a), Value::F32(b)) => a != b,
+		(CmpOpType::Lt_F32, Value::F32(a), Value::F32(b)) => a < b,
+		(CmpOpType::Gt_F32, Value::F32(a), Value::F32(b)) => a > b,
+		(CmpOpType::Le_F32, Value::F32(a), Value::F32(b)) => a <= b,
+		(CmpOpType::Ge_F32, Value::F32(a), Value::F32(b)) => a >= b,
+		(CmpOpType::Eq_F64, Value::F64(a), Value::F64(b)) => a == b,
+		(CmpOpType::Ne_F64, Value::F64(a), Value::F64(b)) => a != b,
+		(CmpOpType::Lt_F64, Value::F64(a), Value::F64(b)) => a < b,
+		(CmpOpType::Gt_F64, Value::F64(a), Value::F64(b)) => a > b,
+		(CmpOpType::Le_F64, Value::F64(a), Value::F64(b)) => a <= b,
+		(CmpOpType::Ge_F64, Value::F64(a), Value::F64(b)) => a >= b,
+		_ => return None,
+	};
+
+	Some(Value::I32(result.into()))
+}
+
+const fn as_value(data: &Expression) -> Option<Value> {
+	if let Expression::Value(value) = data {
+		Some(*value)
+	} else {
+		None
+	}
+}
+
+fn try_fold(data: &Expression) -> Option<Value> {
+	match data {
+		Expression::UnOp(UnOp { op_type, rhs }) => fold_un_op(*op_type, as_value(rhs)?),
+		Expression::BinOp(BinOp { op_type, lhs, rhs }) => {
+			fold_bin_op(*op_type, as_value(lhs)?, as_value(rhs)?)
+		}
+		Expression::CmpOp(CmpOp { op_type, lhs, rhs }) => {
+			fold_cmp_op(*op_type, as_value(lhs)?, as_value(rhs)?)
+		}
+		_ => None,
+	}
+}
+
+fn fold_expression(data: &mut Expression) {
+	match data {
+		Expression::Select(v) => {
+			fold_expression(&mut v.condition);
+			fold_expression(&mut v.on_true);
+			fold_expression(&mut v.on_false);
+		}
+		Expression::LoadAt(v) => fold_expression(&mut v.pointer),
+		Expression::UnOp(v) => fold_expression(&mut v.rhs),
+		Expression::BinOp(v) => {
+			fold_expression(&mut v.lhs);
+			fold_expression(&mut v.rhs);
+		}
+		Expression::CmpOp(v) => {
+			fold_expression(&mut v.lhs);
+			fold_expression(&mut v.rhs);
+		}
+		Expression::GetTemporary(_)
+		| Expression::GetLocal(_)
+		| Expression::GetGlobal(_)
+		| Expression::MemorySize(_)
+		| Expression::Value(_) => {}
+	}
+
+	if let Some(value) = try_fold(data) {
+		*data = Expression::Value(value);
+	}
+}
+
+fn fold_statement(data: &mut Statement) {
+	match data {
+		Statement::Block(v) => fold_block(v),
+		Statement::BrIf(v) => fold_expression(&mut v.condition),
+		Statement::If(v) => {
+			fold_expression(&mut v.condition);
+			fold_block(&mut v.on_true);
+
+			if let Some(v) = &mut v.on_false {
+				fold_block(v);
+			}
+		}
+		Statement::Call(v) => v.param_list.iter_mut().for_each(fold_expression),
+		Statement::CallIndirect(v) => {
+			fold_expression(&mut v.index);
+			v.param_list.iter_mut().for_each(fold_expression);
+		}
+		Statement::SetTemporary(v) => fold_expression(&mut v.value),
+		Statement::SetLocal(v) => fold_expression(&mut v.value),
+		Statement::SetGlobal(v) => fold_expression(&mut v.value),
+		Statement::StoreAt(v) => {
+			fold_expression(&mut v.pointer);
+			fold_expression(&mut v.value);
+		}
+		Statement::MemoryGrow(v) => fold_expression(&mut v.size),
+		Statement::MemoryCopy(v) => {
+			fold_expression(&mut v.destination.pointer);
+			fold_expression(&mut v.source.pointer);
+			fold_expression(&mut v.size);
+		}
+		Statement::MemoryFill(v) => {
+			fold_expression(&mut v.destination.pointer);
+			fold_expression(&mut v.size);
+			fold_expression(&mut v.value);
+		}
+	}
+}
+
+fn fold_block(data: &mut Block) {
+	data.code.iter_mut().for_each(fold_statement);
+
+	if let Some(Terminator::BrTable(v)) = data.last.as_deref_mut() {
+		fold_expression(&mut v.condition);
+	}
+}
+
+/// Folds operations on constants into a single `Value`.
+///
+/// Every `UnOp`, `BinOp`, and `CmpOp` with only `Value` operands is evaluated
+/// following the exact semantics of WebAssembly. Operations that would trap
+/// or involve a NaN are left as they are.
+pub fn fold_constants(func: &mut FuncData) {
+	fold_block(&mut func.code);
+}
@@ -1,4 +1,5 @@
 pub mod factory;
+pub mod fold;
 pub mod module;
 pub mod node;
 pub mod visit;
@@ -66,6 +66,7 @@ pub struct Module<'a> {
 	code_section: Vec<FunctionBody<'a>>,
 
 	name_section: HashMap<u32, &'a str>,
+	local_name_section: HashMap<u32, HashMap<u32, &'a str>>,
 
 	start_section: Option<u32>,
 }
@@ -87,6 +88,7 @@ impl<'a> Module<'a> {
 			data_section: Vec::new(),
 			code_section: Vec::new(),
 			name_section: HashMap::new(),
+			local_name_section: HashMap::new(),
 			start_section: None,
 		};
 
@@ -114,11 +116,26 @@ impl<'a> Module<'a> {
 				}
 				Payload::CustomSection(v) if v.name() == "name" => {
 					for name in NameSectionReader::new(v.data(), v.data_offset()) {
-						if let Name::Function(map) = name? {
-							let mut iter = map.into_iter();
-							while let Some(Ok(elem)) = iter.next() {
-								self.name_section.insert(elem.index, elem.name);
+						match name? {
+							Name::Function(map) => {
+								let mut iter = map.into_iter();
+								while let Some(Ok(elem)) = iter.next() {
+									self.name_section.insert(elem.index, elem.name);
+								}
 							}
+							Name::Local(map) => {
+								let mut iter = map.into_iter();
+								while let Some(Ok(func)) = iter.next() {
+									let list =
+										self.local_name_section.entry(func.index).or_default();
+									let mut iter = func.names.into_iter();
+
+									while let Some(Ok(elem)) = iter.next() {
+										list.insert(elem.index, elem.name);
+									}
+								}
+							}
+							_ => {}
 						}
 					}
 				}
@@ -211,6 +228,11 @@ impl<'a> Module<'a> {
 		&self.name_section
 	}
 
+	#[must_use]
+	pub const fn local_name_section(&self) -> &HashMap<u32, HashMap<u32, &'a str>> {
+		&self.local_name_section
+	}
+
 	#[must_use]
 	pub const fn start_section(&self) -> Option<u32> {
 		self.start_section
@@ -220,6 +242,9 @@ impl<'a> Module<'a> {
 pub struct TypeInfo<'a> {
 	type_list: &'a [Type],
 	func_list: Vec<usize>,
+
+	name_list: &'a HashMap<u32, &'a str>,
+	local_name_list: &'a HashMap<u32, HashMap<u32, &'a str>>,
 }
 
 impl<'a> TypeInfo<'a> {
@@ -228,6 +253,8 @@ impl<'a> TypeInfo<'a> {
 		let mut temp = Self {
 			type_list: &wasm.type_section,
 			func_list: Vec::new(),
+			name_list: &wasm.name_section,
+			local_name_list: &wasm.local_name_section,
 		};
 
 		temp.load_import_list(&wasm.import_section);
@@ -254,6 +281,21 @@ impl<'a> TypeInfo<'a> {
 		self.func_list.extend(iter);
 	}
 
+	#[must_use]
+	pub fn func_name(&self, index: usize) -> Option<&'a str> {
+		let index = u32::try_from(index).ok()?;
+
+		self.name_list.get(&index).copied()
+	}
+
+	#[must_use]
+	pub fn local_name(&self, func: usize, local: usize) -> Option<&'a str> {
+		let func = u32::try_from(func).ok()?;
+		let local = u32::try_from(local).ok()?;
+
+		self.local_name_list.get(&func)?.get(&local).copied()
+	}
+
 	pub(crate) fn by_type_index(&self, index: usize) -> (usize, usize) {
 		let Type::Func(ty) = &self.type_list[index] else {
 			unreachable!("type at func index must be a func type");